@@ -1,8 +1,7 @@
 use std::{
-    collections::VecDeque,
     fs::File,
     io::{Read, Write},
-    time::{Duration, Instant},
+    time::Instant,
 };
 
 use serde::{Deserialize, Serialize};
@@ -13,8 +12,8 @@ use eframe::{
 };
 
 use crate::{
-    hobogo::{Board, Coord, Player},
-    mcts,
+    hobogo::{Action, Board, Coord, Player},
+    mcts, tuner,
 };
 
 const STORAGE_FILE_NAME: &str = "hobogo.json";
@@ -26,6 +25,11 @@ pub struct Settings {
     num_bots: usize,
     humans_first: bool,
     bot_think_time: f32,
+    /// If set, bots bias their MCTS playouts with `rollout_weights` instead
+    /// of sampling moves uniformly at random.
+    trained_bot: bool,
+    rollout_weights: mcts::Weights,
+    theme: ThemeKind,
 }
 
 impl Default for Settings {
@@ -36,21 +40,122 @@ impl Default for Settings {
             num_bots: 1,
             humans_first: true,
             bot_think_time: 1.,
+            trained_bot: false,
+            rollout_weights: [0.0; mcts::NUM_FEATURES],
+            theme: ThemeKind::Classic,
         }
     }
 }
 
+/// Which built-in [`Theme`] is selected; this (not the `Theme` itself, which
+/// holds non-serializable `Color32`s) is what gets persisted.
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ThemeKind {
+    Classic,
+    ColorblindSafe,
+    Dark,
+}
+
+impl ThemeKind {
+    fn all() -> [ThemeKind; 3] {
+        [ThemeKind::Classic, ThemeKind::ColorblindSafe, ThemeKind::Dark]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ThemeKind::Classic => "Classic",
+            ThemeKind::ColorblindSafe => "Colorblind-safe",
+            ThemeKind::Dark => "Dark",
+        }
+    }
+
+    fn theme(self) -> Theme {
+        match self {
+            ThemeKind::Classic => Theme {
+                player_colors: [
+                    Color32::from_rgba_premultiplied(239, 169, 0, 255),
+                    Color32::from_rgba_premultiplied(242, 73, 117, 255),
+                    Color32::from_rgba_premultiplied(31, 187, 171, 255),
+                    Color32::from_rgba_premultiplied(121, 68, 219, 255),
+                ],
+                board_background: Color32::from_rgba_premultiplied(170, 170, 180, 255),
+                free_cell: Color32::from_rgba_premultiplied(150, 150, 160, 255),
+                invalid_cell: Color32::from_rgba_premultiplied(90, 90, 100, 255),
+            },
+            // The Okabe-Ito palette, chosen to stay distinguishable under
+            // the common forms of color blindness.
+            ThemeKind::ColorblindSafe => Theme {
+                player_colors: [
+                    Color32::from_rgba_premultiplied(230, 159, 0, 255),
+                    Color32::from_rgba_premultiplied(0, 114, 178, 255),
+                    Color32::from_rgba_premultiplied(0, 158, 115, 255),
+                    Color32::from_rgba_premultiplied(204, 121, 167, 255),
+                ],
+                board_background: Color32::from_rgba_premultiplied(225, 225, 225, 255),
+                free_cell: Color32::from_rgba_premultiplied(205, 205, 205, 255),
+                invalid_cell: Color32::from_rgba_premultiplied(150, 150, 150, 255),
+            },
+            ThemeKind::Dark => Theme {
+                player_colors: [
+                    Color32::from_rgba_premultiplied(239, 169, 0, 255),
+                    Color32::from_rgba_premultiplied(242, 73, 117, 255),
+                    Color32::from_rgba_premultiplied(31, 187, 171, 255),
+                    Color32::from_rgba_premultiplied(121, 68, 219, 255),
+                ],
+                board_background: Color32::from_rgba_premultiplied(24, 24, 28, 255),
+                free_cell: Color32::from_rgba_premultiplied(60, 60, 68, 255),
+                invalid_cell: Color32::from_rgba_premultiplied(40, 40, 46, 255),
+            },
+        }
+    }
+}
+
+/// Bundles all the colors needed to draw the board: one per player, plus
+/// background/free/invalid-cell colors. Selected via `Settings::theme` and
+/// threaded through `show_board`, `cell_color`, `show_whos_next`, and
+/// `show_score`, replacing the old hardcoded `player_color` free function.
+pub struct Theme {
+    player_colors: [Color32; 4],
+    board_background: Color32,
+    free_cell: Color32,
+    invalid_cell: Color32,
+}
+
+impl Theme {
+    fn player(&self, player: Player) -> Color32 {
+        self.player_colors[player as usize % self.player_colors.len()]
+    }
+}
+
 impl Settings {
     fn num_players(&self) -> usize {
         (self.num_humans + self.num_bots) as usize
     }
 }
 
+/// Playback state for autonomous (bot-vs-bot) games, driven by the toolbar
+/// in [`App::show_playback_controls`].
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum PlayState {
+    Playing,
+    Paused,
+    GameOver,
+}
+
 #[derive(Clone, Deserialize, Serialize)]
 pub struct State {
     settings: Settings,
     board: Board,
     next_player: Player,
+    play_state: PlayState,
+    /// When set, bot moves skip the usual `bot_think_time` pacing so
+    /// fully-automated games can be watched at high speed.
+    fast_forward: bool,
+    /// Every move played so far, in order. Drives the move-list UI, replaces
+    /// the old single-level undo stack (undo is just replaying one move
+    /// fewer), and is what `export_transcript`/`import_transcript` save and
+    /// load.
+    history: Vec<(Player, Action)>,
 }
 
 impl State {
@@ -64,9 +169,109 @@ impl State {
             settings,
             board: Board::new(settings.board_size as i32, settings.board_size as i32),
             next_player: first_player,
+            play_state: PlayState::Playing,
+            fast_forward: false,
+            history: Vec::new(),
+        }
+    }
+
+    /// Moves `play_state` into `GameOver` once the board is full, or back out
+    /// of it if a fresh board was loaded (e.g. via import/undo).
+    fn sync_play_state(&mut self) {
+        if self.board.is_game_over(self.num_players()) {
+            self.play_state = PlayState::GameOver;
+        } else if self.play_state == PlayState::GameOver {
+            self.play_state = PlayState::Playing;
         }
     }
 
+    /// Applies `action` to the board and advances `next_player`, without
+    /// touching `history`. The building block for both `commit_action` (a
+    /// fresh move) and `replay` (rebuilding a board from a known history).
+    fn apply_action(&mut self, player: Player, action: Action) {
+        if let Action::Move(coord) = action {
+            self.board[coord] = Some(player);
+        }
+        self.next_player = (player + 1) % self.num_players() as Player;
+    }
+
+    /// Applies a fresh move and records it in `history`.
+    fn commit_action(&mut self, player: Player, action: Action) {
+        self.apply_action(player, action);
+        self.history.push((player, action));
+    }
+
+    /// Rebuilds a board from scratch by replaying `history` against
+    /// `settings`, so undo can never desync from the move list -- the board
+    /// is always whatever the move list actually produces. Moves are applied
+    /// as given, without legality checks.
+    fn replay(settings: Settings, history: &[(Player, Action)]) -> State {
+        let mut state = State::new(settings);
+        for &(player, action) in history {
+            state.apply_action(player, action);
+        }
+        state.history = history.to_vec();
+        state.sync_play_state();
+        state
+    }
+
+    /// The state one move ago, or `None` at the start of the game.
+    fn step_back(&self) -> Option<State> {
+        if self.history.is_empty() {
+            None
+        } else {
+            Some(State::replay(
+                self.settings,
+                &self.history[..self.history.len() - 1],
+            ))
+        }
+    }
+
+    /// Serializes the game as small JSON: the settings needed to reconstruct
+    /// the board, plus the move list in algebraic notation (e.g. "C3 pass
+    /// A0"), so a transcript is both machine-loadable and readable at a
+    /// glance.
+    fn export_transcript(&self) -> String {
+        let moves = self
+            .history
+            .iter()
+            .map(|&(_, action)| format_action(action))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let transcript = Transcript {
+            settings: self.settings,
+            moves,
+        };
+        serde_json::to_string_pretty(&transcript).unwrap_or_default()
+    }
+
+    /// Parses a transcript produced by `export_transcript` and replays it.
+    /// Stops at the first move that fails to parse or isn't legal for the
+    /// board at that point, so a truncated or hand-edited transcript still
+    /// loads as much of the game as it can.
+    fn import_transcript(text: &str) -> Option<State> {
+        let transcript: Transcript = serde_json::from_str(text).ok()?;
+        let mut state = State::new(transcript.settings);
+        let mut history = Vec::new();
+        for token in transcript.moves.split_whitespace() {
+            let action = match parse_action(token) {
+                Some(action) => action,
+                None => break,
+            };
+            let player = state.next_player;
+            if let Action::Move(coord) = action {
+                if !state.board.is_valid_move(coord, player, state.num_players()) {
+                    break;
+                }
+            }
+            state.apply_action(player, action);
+            history.push((player, action));
+        }
+        state.history = history;
+        state.sync_play_state();
+        Some(state)
+    }
+
     pub fn is_valid(&self) -> bool {
         self.settings.num_players() >= 2 && (self.next_player as usize) < self.num_players()
     }
@@ -114,15 +319,143 @@ impl State {
     }
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Deserialize, Serialize)]
 pub struct App {
     state: State,
 
-    #[serde(skip_serializing)]
-    undo_stack: VecDeque<State>,
+    /// The in-progress background search for the bot's current move, if any.
+    #[serde(skip)]
+    search: Option<BotSearch>,
+
+    /// Set by the "Step" button; consumed by the next bot move, then paused.
+    #[serde(skip)]
+    step_once: bool,
+
+    /// The in-progress genetic tuner run started by "Train", if any.
+    #[serde(skip)]
+    training: Option<TrainingJob>,
+
+    /// On touch devices, the cell selected by a first tap, previewed and
+    /// awaiting a second tap on the same cell to confirm. Unused for mouse
+    /// input, which still commits on a single click.
+    #[serde(skip)]
+    pending_coord: Option<Coord>,
+
+    /// Latches `true` the first time a `egui::Event::Touch` is observed, and
+    /// never clears -- unlike a per-frame "did this frame carry a Touch
+    /// event" check, this survives the case where the synthesized `clicked()`
+    /// for a tap lands in a later frame than its `Touch` event.
+    #[serde(skip)]
+    is_touch_device: bool,
+
+    /// Contents of the move-history text box, used for both
+    /// `export_transcript` and `import_transcript`.
+    #[serde(skip)]
+    transcript_text: String,
+}
+
+/// A bot search in flight: the shared MCTS tree plus the bookkeeping needed
+/// to know when to stop and commit a move.
+struct BotSearch {
+    mcts: mcts::BackgroundSearch,
+    player: Player,
+    #[cfg(target_arch = "wasm32")]
+    start: f64,
+    #[cfg(not(target_arch = "wasm32"))]
+    start: Instant,
+}
+
+/// Generations a native `TrainingJob` runs. Bounded fairly low -- even with
+/// `tuner::POPULATION_SIZE`, a generation plays hundreds of self-play games
+/// through the (now hoisted, but still not free) weighted rollout policy, so
+/// this keeps a background run to roughly a minute rather than letting it
+/// run for many minutes with nothing but a static "Training..." label.
+#[cfg(not(target_arch = "wasm32"))]
+const NATIVE_TRAINING_GENERATIONS: usize = 10;
+
+/// A genetic-tuner run in flight. Native builds run it on a worker thread
+/// since a useful run plays many thousands of self-play games; wasm32 has
+/// no threads, so it runs a small, quick run synchronously on click instead.
+struct TrainingJob {
+    #[cfg(not(target_arch = "wasm32"))]
+    result: std::sync::Arc<std::sync::Mutex<Option<mcts::Weights>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: Option<std::thread::JoinHandle<()>>,
+    /// Generations completed so far, updated from the worker thread so the
+    /// UI can show progress instead of a static "Training..." label.
+    #[cfg(not(target_arch = "wasm32"))]
+    generation: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    #[cfg(target_arch = "wasm32")]
+    result: Option<mcts::Weights>,
+}
+
+impl TrainingJob {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start() -> Self {
+        let result = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let generation = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let worker = {
+            let result = result.clone();
+            let generation = generation.clone();
+            Some(std::thread::spawn(move || {
+                use rand::SeedableRng;
+                use std::sync::atomic::Ordering;
+                let mut rng = rand::rngs::SmallRng::from_entropy();
+                let best = tuner::evolve(
+                    NATIVE_TRAINING_GENERATIONS,
+                    1,
+                    tuner::POPULATION_SIZE,
+                    &mut rng,
+                    |done| generation.store(done, Ordering::Relaxed),
+                );
+                *result.lock().unwrap() = Some(best);
+            }))
+        };
+        TrainingJob {
+            result,
+            worker,
+            generation,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn start() -> Self {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::SmallRng::from_entropy();
+        // No threads on wasm32: run a much smaller synchronous search (fewer
+        // generations over a smaller population) so the page doesn't lock up
+        // on click.
+        let best = tuner::evolve(3, 1, tuner::WASM_POPULATION_SIZE, &mut rng, |_| {});
+        TrainingJob {
+            result: Some(best),
+        }
+    }
 
-    #[serde(skip_serializing)]
-    ai_frame_delay: usize,
+    /// Generations completed so far (out of `NATIVE_TRAINING_GENERATIONS`).
+    /// Native only: on wasm32 the run finishes synchronously in `start`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn generations_done(&self) -> usize {
+        self.generation.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Returns the tuned weights once the run finishes (consuming them).
+    fn poll(&mut self) -> Option<mcts::Weights> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut result = self.result.lock().unwrap();
+            if result.is_some() {
+                if let Some(worker) = self.worker.take() {
+                    let _ = worker.join();
+                }
+                return result.take();
+            }
+            None
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.result.take()
+        }
+    }
 }
 
 impl epi::App for App {
@@ -148,8 +481,12 @@ impl App {
     pub fn restore_or_new() -> Self {
         App {
             state: State::new_or_restore(),
-            undo_stack: Default::default(),
-            ai_frame_delay: 0,
+            search: None,
+            step_once: false,
+            training: None,
+            pending_coord: None,
+            is_touch_device: false,
+            transcript_text: String::new(),
         }
     }
 
@@ -163,21 +500,29 @@ impl App {
             self.state.show_whos_next(ui);
         });
 
+        self.show_playback_controls(ui);
+
         self.show_board_and_interact(ui);
 
         ui.columns(2, |cols| {
             if cols[0].add(Button::new("New Game")).clicked() {
-                if !self.state.board.is_empty() {
-                    self.undo_stack.push_back(self.state.clone());
-                }
                 self.state = State::new(self.state.settings);
                 self.state.save_to_local_storage();
+                self.search = None;
+                self.pending_coord = None;
             }
-            if !self.undo_stack.is_empty() && cols[0].add(Button::new("Undo")).clicked() {
-                self.state = self.undo_stack.pop_back().unwrap();
+            if !self.state.history.is_empty() && cols[0].add(Button::new("Undo")).clicked() {
+                if let Some(previous) = self.state.step_back() {
+                    self.state = previous;
+                    self.state.save_to_local_storage();
+                }
+                self.search = None;
+                self.pending_coord = None;
             }
             self.state.show_score(&mut cols[1]);
         });
+
+        self.show_transcript(ui);
     }
 
     fn show_settings(&mut self, ui: &mut Ui) {
@@ -204,12 +549,93 @@ impl App {
         }
 
         if settings != self.state.settings {
-            if !self.state.board.is_empty() {
-                self.undo_stack.push_back(self.state.clone());
-            }
             self.state = State::new(settings);
             self.state.save_to_local_storage();
         }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.state.settings.trained_bot, "Trained Bot")
+                .on_hover_text(
+                    "Bias bot playouts with weights evolved by self-play, instead of uniform random",
+                );
+
+            if let Some(training) = &self.training {
+                #[cfg(not(target_arch = "wasm32"))]
+                let label = format!(
+                    "Training... ({}/{})",
+                    training.generations_done(),
+                    NATIVE_TRAINING_GENERATIONS
+                );
+                #[cfg(target_arch = "wasm32")]
+                let label = {
+                    let _ = training;
+                    "Training...".to_owned()
+                };
+                ui.add(Label::new(label));
+                ui.ctx().request_repaint();
+            } else if ui.add(Button::new("Train in background")).clicked() {
+                self.training = Some(TrainingJob::start());
+            }
+        });
+
+        let theme_before = self.state.settings.theme;
+        ui.horizontal(|ui| {
+            ui.label("Theme:");
+            for kind in ThemeKind::all() {
+                ui.radio_value(&mut self.state.settings.theme, kind, kind.name());
+            }
+        });
+        if self.state.settings.theme != theme_before {
+            self.state.save_to_local_storage();
+        }
+
+        if let Some(weights) = self.training.as_mut().and_then(TrainingJob::poll) {
+            self.training = None;
+            self.state.settings.rollout_weights = weights;
+            self.state.save_to_local_storage();
+        }
+    }
+
+    /// Toolbar for watching fully-automated (bot-vs-bot) games: pause/play,
+    /// single-step, and fast-forward. Only meaningful while it's a bot's
+    /// turn; a human to move always gets to move immediately.
+    fn show_playback_controls(&mut self, ui: &mut Ui) {
+        if self.state.next_player_is_human() {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            match self.state.play_state {
+                PlayState::Playing => {
+                    if ui.add(Button::new("⏸ Pause")).clicked() {
+                        self.state.play_state = PlayState::Paused;
+                        // Drop (and so stop, via BackgroundSearch's Drop) any
+                        // in-flight search -- the Paused branch of
+                        // show_board_and_interact never calls
+                        // drive_bot_search, so its worker thread would
+                        // otherwise keep spinning at 100% CPU until Play is
+                        // pressed again.
+                        self.search = None;
+                    }
+                }
+                PlayState::Paused => {
+                    if ui.add(Button::new("▶ Play")).clicked() {
+                        self.state.play_state = PlayState::Playing;
+                    }
+                }
+                PlayState::GameOver => {
+                    ui.add(Label::new("Game over"));
+                }
+            }
+
+            if ui.add(Button::new("⏭ Step")).clicked()
+                && self.state.play_state == PlayState::Paused
+            {
+                self.step_once = true;
+            }
+
+            ui.checkbox(&mut self.state.fast_forward, "Fast-forward");
+        });
     }
 
     fn show_board_and_interact(&mut self, ui: &mut Ui) {
@@ -223,56 +649,210 @@ impl App {
         // HACK: Add some spacing for the column names
         ui.add_space(32.0);
 
-        let state = &mut self.state;
+        self.state.sync_play_state();
 
-        if !state.board.is_game_over(state.num_players()) {
-            if state.next_player_is_human() {
-                if board_interact.hovered() {
+        if !self.state.next_player_is_human() {
+            // Only a human's own turn keeps a pending tap selection alive.
+            self.pending_coord = None;
+        }
+
+        if self.state.play_state != PlayState::GameOver {
+            if self.state.next_player_is_human() {
+                self.search = None; // A human move cancels any stale bot search.
+
+                // Touch devices get two-stage tap-to-confirm (first tap
+                // selects and previews, second tap on the same cell commits)
+                // since there's no hover to preview a move before committing.
+                // A mouse still commits on a single click, exactly as before.
+                // Latch rather than re-derive every frame: if the
+                // synthesized `clicked()` for a tap doesn't land in the same
+                // frame as its `Touch` event, a same-frame-only check would
+                // read `false` on the commit frame and the move would commit
+                // on the first tap, defeating tap-to-confirm.
+                if ui
+                    .input()
+                    .events
+                    .iter()
+                    .any(|event| matches!(event, egui::Event::Touch { .. }))
+                {
+                    self.is_touch_device = true;
+                }
+                let is_touch = self.is_touch_device;
+
+                if board_interact.clicked() {
                     if let Some(mouse_pos) = ui.input().pointer.interact_pos() {
-                        if let Some(hovered_coord) = hovered_coord(&state.board, &rect, mouse_pos) {
-                            if state.board.is_valid_move(
-                                hovered_coord,
-                                state.next_player,
-                                state.num_players(),
-                            ) {
-                                if board_interact.clicked() {
-                                    self.undo_stack.push_back(state.clone());
-                                    state.board[hovered_coord] = Some(state.next_player);
-                                    state.next_player =
-                                        (state.next_player + 1) % (state.num_players() as u8);
-                                    state.save_to_local_storage();
-                                } else {
-                                    let mut preview = state.clone();
-                                    preview.board[hovered_coord] = Some(state.next_player);
-                                    return preview.show_board(rect, ui.painter());
-                                }
+                        if let Some(coord) = hovered_coord(&self.state.board, &rect, mouse_pos) {
+                            let is_legal = self.state.board.is_valid_move(
+                                coord,
+                                self.state.next_player,
+                                self.state.num_players(),
+                            );
+                            if !is_legal {
+                                self.pending_coord = None;
+                            } else if !is_touch || self.pending_coord == Some(coord) {
+                                let state = &mut self.state;
+                                let player = state.next_player;
+                                state.commit_action(player, Action::Move(coord));
+                                state.save_to_local_storage();
+                                self.pending_coord = None;
+                            } else {
+                                // First tap: select and preview, wait for confirmation.
+                                self.pending_coord = Some(coord);
                             }
                         }
                     }
                 }
-            } else {
-                if ui.ctx().is_using_pointer() {
-                    // Don't do anything slow while the user is e.g. dragging a slider
-                } else {
-                    // This is slow. TODO: run in background thread... when wasm supports it.
-
-                    if self.ai_frame_delay < 6 {
-                        // HACK: Give WebGL time to catch up visually
-                        self.ai_frame_delay += 1;
-                    } else {
-                        self.ai_frame_delay = 0;
 
-                        if let Some(coord) = state.ai_move(state.next_player, state.num_players()) {
-                            state.board[coord] = Some(state.next_player);
-                        }
-                        state.next_player = (state.next_player + 1) % (state.num_players() as u8);
-                    }
+                // `pending_coord` is only ever set on a touch device (see
+                // above), and it stays `Some` across the touch-up between the
+                // first and confirming tap, so drive the preview from it
+                // whenever it's set rather than from `is_touch`.
+                let hovered_or_pending = if self.pending_coord.is_some() {
+                    self.pending_coord
+                } else if board_interact.hovered() {
+                    ui.input()
+                        .pointer
+                        .interact_pos()
+                        .and_then(|pos| hovered_coord(&self.state.board, &rect, pos))
+                } else {
+                    None
+                };
+
+                let preview_coord = hovered_or_pending.filter(|&c| {
+                    self.state
+                        .board
+                        .is_valid_move(c, self.state.next_player, self.state.num_players())
+                });
+
+                if let Some(coord) = preview_coord {
+                    let mut preview = self.state.clone();
+                    preview.board[coord] = Some(self.state.next_player);
+                    return preview.show_board(rect, ui.painter());
+                }
+            } else if ui.ctx().is_using_pointer() {
+                // Don't do anything slow while the user is e.g. dragging a slider
+            } else if self.state.play_state == PlayState::Playing || self.step_once {
+                if self.drive_bot_search() && self.step_once {
+                    self.step_once = false;
+                    self.state.play_state = PlayState::Paused;
                 }
                 ui.ctx().request_repaint();
             }
         }
 
-        state.show_board(rect, ui.painter());
+        self.state.show_board(rect, ui.painter());
+
+        if let Some(search) = &self.search {
+            let visit_counts = search.mcts.visit_counts();
+            self.state
+                .show_search_heatmap(rect, ui.painter(), &visit_counts);
+        }
+    }
+
+    /// Starts (or continues) the background search for the current bot's
+    /// move, and commits the move once `bot_think_time` has elapsed.
+    ///
+    /// On native targets the search runs on a worker thread against a
+    /// `Mutex`-shared tree; we just poll it here. On wasm32, which has no
+    /// threads, we instead run a small bounded number of iterations per
+    /// frame against that same shared tree, so the UI never blocks.
+    fn drive_bot_search(&mut self) -> bool {
+        let player = self.state.next_player;
+        let num_players = self.state.num_players();
+        let think_time = if self.state.fast_forward {
+            // Ignore the configured think time and commit as soon as the
+            // search has had at least a brief moment to run.
+            self.state.settings.bot_think_time.min(0.05)
+        } else {
+            self.state.settings.bot_think_time
+        };
+
+        let needs_new_search = !matches!(&self.search, Some(search) if search.player == player);
+        if needs_new_search {
+            let game_state = mcts::GameState {
+                board: self.state.board.clone(),
+                next_player: player,
+                num_players,
+            };
+            let search = if self.state.settings.trained_bot {
+                mcts::BackgroundSearch::start_with_weights(
+                    game_state,
+                    self.state.settings.rollout_weights,
+                )
+            } else {
+                mcts::BackgroundSearch::start(game_state)
+            };
+            self.search = Some(BotSearch {
+                mcts: search,
+                player,
+                #[cfg(target_arch = "wasm32")]
+                start: egui_web::now_sec(),
+                #[cfg(not(target_arch = "wasm32"))]
+                start: Instant::now(),
+            });
+        }
+
+        let search = self.search.as_ref().unwrap();
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use rand::SeedableRng;
+            let mut rng = rand::rngs::SmallRng::from_entropy();
+            // A handful of iterations per frame is enough to keep the tree
+            // growing without ever causing a visible stutter.
+            search.mcts.step(&mut rng, 32);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let elapsed = egui_web::now_sec() - search.start;
+        #[cfg(not(target_arch = "wasm32"))]
+        let elapsed = (Instant::now() - search.start).as_secs_f32() as f64;
+
+        if elapsed >= think_time as f64 {
+            let action = search.mcts.best_action().unwrap_or(Action::Pass);
+            self.search = None;
+
+            let state = &mut self.state;
+            state.commit_action(player, action);
+            state.save_to_local_storage();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move list, and JSON export/import, for the current game.
+    fn show_transcript(&mut self, ui: &mut Ui) {
+        ui.collapsing("Move history", |ui| {
+            egui::ScrollArea::from_max_height(150.0).show(ui, |ui| {
+                for &(player, action) in &self.state.history {
+                    let color = self.state.theme().player(player);
+                    ui.add(
+                        Label::new(format!(
+                            "{}: {}",
+                            self.state.player_name(player),
+                            format_action(action)
+                        ))
+                        .text_color(color),
+                    );
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.add(Button::new("Export")).clicked() {
+                    self.transcript_text = self.state.export_transcript();
+                }
+                if ui.add(Button::new("Import")).clicked() {
+                    if let Some(imported) = State::import_transcript(&self.transcript_text) {
+                        self.state = imported;
+                        self.state.save_to_local_storage();
+                        self.search = None;
+                        self.pending_coord = None;
+                    }
+                }
+            });
+            ui.add(TextEdit::multiline(&mut self.transcript_text));
+        });
     }
 }
 
@@ -281,7 +861,7 @@ impl State {
         if self.board.is_game_over(self.num_players()) {
             ui.add(Label::new("Game over!"));
         } else {
-            let player_color = player_color(self.next_player);
+            let player_color = self.theme().player(self.next_player);
             let player_name = self.player_name(self.next_player);
             if self.next_player_is_human() {
                 ui.add(Label::new(format!("{} to play", player_name)).text_color(player_color));
@@ -294,10 +874,11 @@ impl State {
     }
 
     pub fn show_score(&mut self, ui: &mut Ui) {
+        let theme = self.theme();
         ui.columns(2, |cols| {
-            let score = self.board.points();
+            let score = self.board.points(self.num_players());
             for pi in 0..self.num_players() {
-                let player_color = player_color(pi as Player);
+                let player_color = theme.player(pi as Player);
                 let player_name = self.player_name(pi as Player);
                 cols[0].add(Label::new(format!("{}", player_name)).text_color(player_color));
                 cols[1].add(Label::new(format!("{}", score[pi])).text_color(player_color));
@@ -305,7 +886,7 @@ impl State {
         });
 
         /*
-        let score = self.board.points();
+        let score = self.board.points(self.num_players());
         let mut cursor = ui.cursor();
         for pi in 0..self.num_players() {
             let player_color = player_color(pi as Player);
@@ -333,6 +914,10 @@ impl State {
         self.settings.num_players()
     }
 
+    fn theme(&self) -> Theme {
+        self.settings.theme.theme()
+    }
+
     fn is_human(&self, player: Player) -> bool {
         (player as usize) < self.settings.num_humans
     }
@@ -359,18 +944,21 @@ impl State {
 
     fn show_board(&self, rect: Rect, painter: &Painter) {
         let board = &self.board;
+        let theme = self.theme();
         let spacing = rect.width() / (board.width as f32);
         let volatile = board.volatile_cells(self.num_players());
 
         let cell_side = spacing * 0.84;
         let corner_radius = (cell_side * 0.25).round();
 
+        painter.rect_filled(rect, 0.0, theme.board_background);
+
         if self.next_player_is_human() {
             // Highlight who is to play next
             painter.rect_stroke(
                 rect.expand(4.0),
                 corner_radius * 2.0f32.sqrt(),
-                (2.0, player_color(self.next_player)),
+                (2.0, theme.player(self.next_player)),
             );
         }
 
@@ -378,7 +966,7 @@ impl State {
             let center = rect.min + spacing * vec2(c.x as f32 + 0.5, c.y as f32 + 0.5);
 
             let is_volatile = volatile[board.index(c).unwrap()];
-            let fill = self.cell_color(c, is_volatile);
+            let fill = self.cell_color(&theme, c, is_volatile);
 
             if let Some(_player) = board[c] {
                 let rect = Rect::from_center_size(center, vec2(cell_side, cell_side));
@@ -413,10 +1001,10 @@ impl State {
         }
     }
 
-    fn cell_color(&self, c: Coord, is_volatile: bool) -> Color32 {
+    fn cell_color(&self, theme: &Theme, c: Coord, is_volatile: bool) -> Color32 {
         let influence = self.board.influence(c);
         if let Some(claimer) = influence.player() {
-            let color = player_color(claimer);
+            let color = theme.player(claimer);
             if is_volatile || influence.is_occupied() {
                 color
             } else {
@@ -433,67 +1021,34 @@ impl State {
                 .is_valid_move(c, self.next_player, self.num_players())
         {
             // The currant human can't move here
-            Color32::from_rgba_premultiplied(90, 90, 100, 255)
+            theme.invalid_cell
         } else {
             // Free (at least for some)
-            Color32::from_rgba_premultiplied(150, 150, 160, 255)
+            theme.free_cell
         }
     }
 
-    pub fn ai_move(&self, player: Player, num_players: usize) -> Option<Coord> {
-        use rand::SeedableRng;
-        let mut rng = rand::rngs::SmallRng::from_entropy(); // Fast
-
-        let state = mcts::GameState {
-            next_player: player,
-            num_players,
-            board: self.board.clone(),
-        };
-
-        let think_time = self.settings.bot_think_time;
-        let mut mcts = mcts::Mcts::new(state);
-        #[cfg(target_arch = "wasm32")]
-        let start = egui_web::now_sec();
-        #[cfg(not(target_arch = "wasm32"))]
-        let start = Instant::now();
-        while {
-            mcts.iterate(&mut rng);
-            #[cfg(target_arch = "wasm32")]
-            {
-                egui_web::now_sec() - start < think_time as f64
-            }
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                Instant::now() - start < Duration::from_secs_f32(think_time)
-            }
-        } {}
-
-        let action = mcts.best_action().cloned();
-
-        if let Some(action) = action {
-            match action {
-                mcts::Action::Pass => None,
-                mcts::Action::Move(coord) => Some(coord),
+    /// Draws a translucent overlay over the board where cell brightness is
+    /// proportional to how many MCTS visits that move has received so far,
+    /// so the user can watch the bot's search converge in real time.
+    fn show_search_heatmap(&self, rect: Rect, painter: &Painter, visit_counts: &[(mcts::Action, u32)]) {
+        let spacing = rect.width() / (self.board.width as f32);
+        let max_visits = visit_counts.iter().map(|&(_, v)| v).max().unwrap_or(0).max(1);
+
+        for &(action, visits) in visit_counts {
+            if let mcts::Action::Move(c) = action {
+                let center = rect.min + spacing * vec2(c.x as f32 + 0.5, c.y as f32 + 0.5);
+                let alpha = (visits as f32 / max_visits as f32 * 180.0) as u8;
+                painter.circle_filled(
+                    center,
+                    0.45 * spacing,
+                    Color32::from_rgba_premultiplied(255, 255, 255, alpha),
+                );
             }
-        } else {
-            None
         }
     }
 }
 
-fn player_color(player: Player) -> Color32 {
-    match player {
-        // 0 => Color32::from_rgba_premultiplied(85, 119, 255, 255),
-        // 1 => Color32::from_rgba_premultiplied(205, 0, 0, 255),
-        // 2 => Color32::from_rgba_premultiplied(0, 255, 0, 255),
-        // _ => Color32::from_rgba_premultiplied(221, 221, 0, 255),
-        0 => Color32::from_rgba_premultiplied(239, 169, 0, 255),
-        1 => Color32::from_rgba_premultiplied(242, 73, 117, 255),
-        2 => Color32::from_rgba_premultiplied(31, 187, 171, 255),
-        _ => Color32::from_rgba_premultiplied(121, 68, 219, 255),
-    }
-}
-
 /// Chess coordinate name
 fn column_name(x: i32) -> String {
     ((65 + (x as u8)) as char).to_string()
@@ -504,6 +1059,38 @@ fn row_name(y: i32) -> String {
     y.to_string()
 }
 
+/// A full game transcript: the settings needed to reconstruct the board,
+/// plus every move in algebraic notation. Exported/imported as JSON through
+/// the text box in [`App::show_transcript`].
+#[derive(Deserialize, Serialize)]
+struct Transcript {
+    settings: Settings,
+    /// Space-separated algebraic moves, e.g. `"C3 pass A0"`.
+    moves: String,
+}
+
+fn format_action(action: Action) -> String {
+    match action {
+        Action::Pass => "pass".to_string(),
+        Action::Move(c) => format!("{}{}", column_name(c.x), row_name(c.y)),
+    }
+}
+
+/// The inverse of `format_action`.
+fn parse_action(token: &str) -> Option<Action> {
+    if token.eq_ignore_ascii_case("pass") {
+        return Some(Action::Pass);
+    }
+    let mut chars = token.chars();
+    let column = chars.next()?;
+    if !column.is_ascii_uppercase() {
+        return None;
+    }
+    let x = (column as u8 - b'A') as i32;
+    let y: i32 = chars.as_str().parse().ok()?;
+    Some(Action::Move(Coord::new(x, y)))
+}
+
 fn hovered_coord(board: &Board, rect: &Rect, mouse_pos: Pos2) -> Option<Coord> {
     let spacing = rect.width() / (board.width as f32);
     for c in board.coords() {
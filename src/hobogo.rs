@@ -0,0 +1,175 @@
+//! Core game logic for Hobogo: a small Go-like territory game on a grid.
+//!
+//! Players place stones one at a time. Empty cells are "owned" by whichever
+//! player has the nearest stone(s); a cell equidistant between two or more
+//! players is `volatile` (contested) and doesn't count towards anyone's
+//! score until the tie is broken.
+
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+
+pub type Player = u8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Coord {
+    pub fn new(x: i32, y: i32) -> Self {
+        Coord { x, y }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Action {
+    Pass,
+    Move(Coord),
+}
+
+/// The influence a cell receives from the nearest stone(s).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Influence {
+    closest_player: Option<Player>,
+    is_tie: bool,
+    is_occupied: bool,
+}
+
+impl Influence {
+    /// The player who currently controls this cell, if any (`None` if tied or unclaimed).
+    pub fn player(&self) -> Option<Player> {
+        if self.is_tie {
+            None
+        } else {
+            self.closest_player
+        }
+    }
+
+    /// True if the cell has a stone on it (as opposed to merely being influenced).
+    pub fn is_occupied(&self) -> bool {
+        self.is_occupied
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Board {
+    pub width: i32,
+    pub height: i32,
+    cells: Vec<Option<Player>>,
+}
+
+impl Board {
+    pub fn new(width: i32, height: i32) -> Self {
+        Board {
+            width,
+            height,
+            cells: vec![None; (width * height) as usize],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|c| c.is_none())
+    }
+
+    pub fn is_inside(&self, c: Coord) -> bool {
+        c.x >= 0 && c.y >= 0 && c.x < self.width && c.y < self.height
+    }
+
+    pub fn index(&self, c: Coord) -> Option<usize> {
+        if self.is_inside(c) {
+            Some((c.y * self.width + c.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        let width = self.width;
+        let height = self.height;
+        (0..height).flat_map(move |y| (0..width).map(move |x| Coord::new(x, y)))
+    }
+
+    pub fn is_valid_move(&self, c: Coord, _player: Player, _num_players: usize) -> bool {
+        self.index(c).map_or(false, |i| self.cells[i].is_none())
+    }
+
+    pub fn is_game_over(&self, num_players: usize) -> bool {
+        num_players < 2 || self.coords().all(|c| !self.is_valid_move(c, 0, num_players))
+    }
+
+    pub fn influence(&self, c: Coord) -> Influence {
+        if let Some(player) = self[c] {
+            return Influence {
+                closest_player: Some(player),
+                is_tie: false,
+                is_occupied: true,
+            };
+        }
+
+        let mut best_dist = i32::MAX;
+        let mut best_player = None;
+        let mut is_tie = false;
+
+        for other in self.coords() {
+            if let Some(player) = self[other] {
+                let dist = (other.x - c.x).abs() + (other.y - c.y).abs();
+                if dist < best_dist {
+                    best_dist = dist;
+                    best_player = Some(player);
+                    is_tie = false;
+                } else if dist == best_dist && best_player != Some(player) {
+                    is_tie = true;
+                }
+            }
+        }
+
+        Influence {
+            closest_player: best_player,
+            is_tie,
+            is_occupied: false,
+        }
+    }
+
+    /// Cells whose ownership is currently contested (a tie in influence distance).
+    pub fn volatile_cells(&self, _num_players: usize) -> Vec<bool> {
+        self.coords()
+            .map(|c| {
+                let influence = self.influence(c);
+                !influence.is_occupied() && influence.player().is_none() && {
+                    // Distinguish "tied" from "no stones yet".
+                    self.coords().any(|other| self[other].is_some())
+                }
+            })
+            .collect()
+    }
+
+    /// Territory score for each of `num_players` players, indices
+    /// `0..num_players`. Always sized by `num_players` (not by the highest
+    /// player index seen on the board so far) so callers can safely index it
+    /// even on an empty board or early in the game.
+    pub fn points(&self, num_players: usize) -> Vec<i64> {
+        let mut points = vec![0i64; num_players];
+        for c in self.coords() {
+            if let Some(player) = self.influence(c).player() {
+                points[player as usize] += 1;
+            }
+        }
+        points
+    }
+}
+
+impl Index<Coord> for Board {
+    type Output = Option<Player>;
+    fn index(&self, c: Coord) -> &Option<Player> {
+        &self.cells[self.index(c).expect("coord out of bounds")]
+    }
+}
+
+impl IndexMut<Coord> for Board {
+    fn index_mut(&mut self, c: Coord) -> &mut Option<Player> {
+        let i = self.index(c).expect("coord out of bounds");
+        &mut self.cells[i]
+    }
+}
@@ -0,0 +1,449 @@
+//! Monte Carlo Tree Search for Hobogo.
+//!
+//! The search tree lives behind a single `Arc<Mutex<Mcts>>` (see
+//! [`BackgroundSearch`]) so a worker thread can keep running search
+//! iterations while the UI thread takes the lock just long enough to read
+//! visit counts for a heat-map or to pick a move. The worker takes the lock
+//! for each iteration's tree walk and expansion, releases it for the
+//! rollout, and takes it again for the backpropagation; it is the only
+//! thread that mutates the tree.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use rand::Rng;
+
+use crate::hobogo::{Board, Coord, Player};
+
+pub use crate::hobogo::Action;
+
+const EXPLORATION: f32 = 1.4142135;
+
+/// Number of hand-picked features used to bias the rollout policy. See
+/// [`action_features`].
+pub const NUM_FEATURES: usize = 3;
+
+/// A rollout policy bias: one weight per feature in [`action_features`].
+/// The all-zero vector (the `Default`) makes every move equally likely,
+/// which is exactly the old uniform-random rollout -- so an untrained
+/// weight vector degrades gracefully rather than playing badly.
+pub type Weights = [f32; NUM_FEATURES];
+
+#[derive(Clone)]
+pub struct GameState {
+    pub board: Board,
+    pub next_player: Player,
+    pub num_players: usize,
+}
+
+impl GameState {
+    pub(crate) fn legal_actions(&self) -> Vec<Action> {
+        let mut actions: Vec<Action> = self
+            .board
+            .coords()
+            .filter(|&c| {
+                self.board
+                    .is_valid_move(c, self.next_player, self.num_players)
+            })
+            .map(Action::Move)
+            .collect();
+        if actions.is_empty() {
+            actions.push(Action::Pass);
+        }
+        actions
+    }
+
+    pub(crate) fn apply(&self, action: Action) -> GameState {
+        let mut next = self.clone();
+        if let Action::Move(coord) = action {
+            next.board[coord] = Some(self.next_player);
+        }
+        next.next_player = (self.next_player + 1) % self.num_players as Player;
+        next
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.board.is_game_over(self.num_players)
+    }
+}
+
+/// The parts of [`action_features`] that only depend on `state`, not on the
+/// candidate action -- computed once per [`weighted_choice`] call rather
+/// than once per legal move.
+struct StateFeatures {
+    /// `state.board.volatile_cells(state.num_players)`.
+    volatile: Vec<bool>,
+    /// Count of cells already influenced by `state.next_player`.
+    influence: f32,
+}
+
+fn state_features(state: &GameState) -> StateFeatures {
+    StateFeatures {
+        volatile: state.board.volatile_cells(state.num_players),
+        influence: state
+            .board
+            .coords()
+            .filter(|&c| state.board.influence(c).player() == Some(state.next_player))
+            .count() as f32,
+    }
+}
+
+/// Features used to bias rollout move selection, in the order matched by
+/// [`Weights`]:
+/// 1. Distance to the mover's nearest own stone (0 for the first move).
+/// 2. How many cells the move would flip from settled to contested
+///    (`Board::volatile_cells`).
+/// 3. The mover's influence-cell gain from playing here.
+///
+/// `Action::Pass` always scores zero on every feature. `before` is
+/// [`state_features`]`(state)`, passed in so callers scoring many actions
+/// against the same `state` compute it only once.
+fn action_features(state: &GameState, action: Action, before: &StateFeatures) -> Weights {
+    let coord = match action {
+        Action::Pass => return [0.0; NUM_FEATURES],
+        Action::Move(coord) => coord,
+    };
+
+    let nearest_own_stone = state
+        .board
+        .coords()
+        .filter(|&c| state.board[c] == Some(state.next_player))
+        .map(|c| manhattan_distance(c, coord))
+        .fold(f32::INFINITY, f32::min);
+    let nearest_own_stone = if nearest_own_stone.is_finite() {
+        nearest_own_stone
+    } else {
+        0.0
+    };
+
+    let mut after = state.board.clone();
+    after[coord] = Some(state.next_player);
+
+    let volatile_after = after.volatile_cells(state.num_players);
+    let newly_volatile = before
+        .volatile
+        .iter()
+        .zip(volatile_after.iter())
+        .filter(|&(&before, &after)| after && !before)
+        .count() as f32;
+
+    let influence_after = after
+        .coords()
+        .filter(|&c| after.influence(c).player() == Some(state.next_player))
+        .count() as f32;
+    let influence_delta = influence_after - before.influence;
+
+    [nearest_own_stone, newly_volatile, influence_delta]
+}
+
+fn manhattan_distance(a: Coord, b: Coord) -> f32 {
+    ((a.x - b.x).abs() + (a.y - b.y).abs()) as f32
+}
+
+/// Weights this close to zero are treated as "no bias at all" (see
+/// [`weighted_choice`]), so an untrained [`Weights`] doesn't pay for feature
+/// computation it can't use.
+const ZERO_WEIGHTS_EPSILON: f32 = 1e-6;
+
+/// Samples a legal action with probability proportional to
+/// `softmax(weights . action_features(state, action))`.
+///
+/// When `weights` is all ~0 (the untrained default), every action would
+/// score equally anyway, so this skips [`action_features`] entirely and
+/// picks uniformly at random -- the same cheap rollout chunk0-1 shipped.
+pub fn weighted_choice(
+    state: &GameState,
+    actions: &[Action],
+    weights: &Weights,
+    rng: &mut impl Rng,
+) -> Action {
+    if weights.iter().all(|w| w.abs() < ZERO_WEIGHTS_EPSILON) {
+        return actions[rng.gen_range(0..actions.len())];
+    }
+
+    let before = state_features(state);
+    let scores: Vec<f32> = actions
+        .iter()
+        .map(|&action| {
+            let features = action_features(state, action, &before);
+            features.iter().zip(weights).map(|(f, w)| f * w).sum()
+        })
+        .collect();
+
+    let max_score = scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let exp_scores: Vec<f32> = scores.iter().map(|s| (s - max_score).exp()).collect();
+    let total: f32 = exp_scores.iter().sum();
+
+    let mut threshold = rng.gen_range(0.0..total);
+    for (&action, &exp_score) in actions.iter().zip(&exp_scores) {
+        if threshold < exp_score {
+            return action;
+        }
+        threshold -= exp_score;
+    }
+    *actions.last().expect("there is always at least Pass")
+}
+
+#[derive(Default, Clone, Copy)]
+struct NodeStats {
+    visits: u32,
+    wins: f32,
+}
+
+struct Node {
+    action: Option<Action>,
+    player_just_moved: Option<Player>,
+    state: GameState,
+    stats: NodeStats,
+    children: Vec<Node>,
+    untried: Vec<Action>,
+}
+
+impl Node {
+    fn new(state: GameState, action: Option<Action>, player_just_moved: Option<Player>) -> Self {
+        Node {
+            untried: state.legal_actions(),
+            action,
+            player_just_moved,
+            state,
+            stats: NodeStats::default(),
+            children: Vec::new(),
+        }
+    }
+
+    fn uct_score(&self, parent_visits: u32) -> f32 {
+        if self.stats.visits == 0 {
+            return f32::INFINITY;
+        }
+        let mean = self.stats.wins / self.stats.visits as f32;
+        mean + EXPLORATION * ((parent_visits as f32).ln() / self.stats.visits as f32).sqrt()
+    }
+
+    fn select_child(&mut self) -> &mut Node {
+        let parent_visits = self.stats.visits;
+        self.children
+            .iter_mut()
+            .max_by(|a, b| {
+                a.uct_score(parent_visits)
+                    .partial_cmp(&b.uct_score(parent_visits))
+                    .unwrap()
+            })
+            .expect("select_child called on a node with no children")
+    }
+
+    fn expand(&mut self, rng: &mut impl Rng) -> &mut Node {
+        let i = rng.gen_range(0..self.untried.len());
+        let action = self.untried.swap_remove(i);
+        let player_just_moved = self.state.next_player;
+        let child_state = self.state.apply(action);
+        self.children
+            .push(Node::new(child_state, Some(action), Some(player_just_moved)));
+        self.children.last_mut().unwrap()
+    }
+
+    fn backprop(&mut self, winner: Option<Player>) {
+        self.stats.visits += 1;
+        if self.player_just_moved.is_some() && self.player_just_moved == winner {
+            self.stats.wins += 1.0;
+        }
+    }
+}
+
+fn weighted_rollout(mut state: GameState, weights: &Weights, rng: &mut impl Rng) -> Option<Player> {
+    let mut passes_in_a_row = 0;
+    while !state.is_game_over() && passes_in_a_row < state.num_players {
+        let actions = state.legal_actions();
+        let action = weighted_choice(&state, &actions, weights, rng);
+        passes_in_a_row = if action == Action::Pass {
+            passes_in_a_row + 1
+        } else {
+            0
+        };
+        state = state.apply(action);
+    }
+    let points = state.board.points(state.num_players);
+    points
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, &p)| p)
+        .map(|(player, _)| player as Player)
+}
+
+/// A single-threaded search tree. See [`BackgroundSearch`] for the
+/// thread-safe wrapper used by the UI.
+pub struct Mcts {
+    root: Node,
+    rollout_weights: Weights,
+}
+
+impl Mcts {
+    pub fn new(state: GameState) -> Self {
+        Self::new_with_weights(state, [0.0; NUM_FEATURES])
+    }
+
+    /// Like [`Mcts::new`], but biases playouts with `rollout_weights` (see
+    /// [`action_features`]) instead of picking uniformly at random.
+    pub fn new_with_weights(state: GameState, rollout_weights: Weights) -> Self {
+        Mcts {
+            root: Node::new(state, None, None),
+            rollout_weights,
+        }
+    }
+
+    pub fn iterate(&mut self, rng: &mut impl Rng) {
+        let (state, path) = self.select_and_expand(rng);
+        let winner = weighted_rollout(state, &self.rollout_weights, rng);
+        self.backprop(path, winner);
+    }
+
+    /// The selection/expansion half of [`Mcts::iterate`]: walks down the
+    /// tree (creating at most one new node) and returns the state to roll
+    /// out from plus the path of nodes to [`Mcts::backprop`] into once the
+    /// rollout's winner is known.
+    ///
+    /// Split out from `iterate` so [`BackgroundSearch`]'s worker can hold the
+    /// shared lock only for this cheap O(tree depth) step and the matching
+    /// `backprop`, running the expensive rollout itself unlocked.
+    fn select_and_expand(&mut self, rng: &mut impl Rng) -> (GameState, Vec<*mut Node>) {
+        let mut path = vec![&mut self.root as *mut Node];
+        // SAFETY: we only ever hold one mutable reference at a time while
+        // walking down the tree, following the path we record in `path`.
+        let mut node: &mut Node = unsafe { &mut *path[0] };
+        while node.untried.is_empty() && !node.children.is_empty() {
+            node = node.select_child();
+            path.push(node as *mut Node);
+        }
+
+        if !node.untried.is_empty() && !node.state.is_game_over() {
+            node = node.expand(rng);
+            path.push(node as *mut Node);
+        }
+
+        (node.state.clone(), path)
+    }
+
+    /// Backpropagates a rollout's `winner` up the `path` returned by
+    /// [`Mcts::select_and_expand`]. The caller must not have mutated the
+    /// tree (e.g. via another `select_and_expand`) in between, or `path`'s
+    /// pointers may no longer point at the nodes they were recorded for.
+    fn backprop(&mut self, path: Vec<*mut Node>, winner: Option<Player>) {
+        for node_ptr in path.into_iter().rev() {
+            // SAFETY: nodes are visited in the same order they were pushed,
+            // and no other reference to them is alive at this point.
+            unsafe { &mut *node_ptr }.backprop(winner);
+        }
+    }
+
+    /// The most-visited legal action from the root (the standard MCTS choice:
+    /// visit count is a less noisy signal than average win rate).
+    pub fn best_action(&self) -> Option<&Action> {
+        self.root
+            .children
+            .iter()
+            .max_by_key(|child| child.stats.visits)
+            .and_then(|child| child.action.as_ref())
+    }
+
+    /// Visit counts for each immediate child, keyed by the move that leads to
+    /// it. Used by the UI to draw a live heat-map while the search runs.
+    pub fn visit_counts(&self) -> Vec<(Action, u32)> {
+        self.root
+            .children
+            .iter()
+            .filter_map(|child| child.action.map(|action| (action, child.stats.visits)))
+            .collect()
+    }
+
+    pub fn total_iterations(&self) -> u32 {
+        self.root.stats.visits
+    }
+}
+
+pub type SharedMcts = Arc<Mutex<Mcts>>;
+
+/// Owns a search tree shared with a background worker thread (native) or
+/// stepped incrementally from the UI thread (wasm32, which has no threads).
+pub struct BackgroundSearch {
+    shared: SharedMcts,
+    stop: Arc<AtomicBool>,
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: Option<std::thread::JoinHandle<()>>,
+}
+
+impl BackgroundSearch {
+    /// Starts a search with uniform-random playouts.
+    pub fn start(state: GameState) -> Self {
+        Self::start_with_weights(state, [0.0; NUM_FEATURES])
+    }
+
+    /// Starts a search whose playouts are biased by `rollout_weights` (the
+    /// "Trained Bot" mode -- see `Settings::trained_bot`).
+    pub fn start_with_weights(state: GameState, rollout_weights: Weights) -> Self {
+        let shared = Arc::new(Mutex::new(Mcts::new_with_weights(state, rollout_weights)));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let worker = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            Some(std::thread::spawn(move || {
+                use rand::SeedableRng;
+                let mut rng = rand::rngs::SmallRng::from_entropy();
+                while !stop.load(Ordering::Relaxed) {
+                    // Only hold the lock for the cheap tree walk on either
+                    // side of the rollout, not for the rollout itself (a
+                    // full game playout), so the UI thread's visit_counts()/
+                    // best_action() never stall behind one.
+                    let (state, path) = shared.lock().unwrap().select_and_expand(&mut rng);
+                    let winner = weighted_rollout(state, &rollout_weights, &mut rng);
+                    shared.lock().unwrap().backprop(path, winner);
+                }
+            }))
+        };
+
+        BackgroundSearch {
+            shared,
+            stop,
+            #[cfg(not(target_arch = "wasm32"))]
+            worker,
+        }
+    }
+
+    /// wasm32 has no threads, so `App::update` calls this once per frame
+    /// instead to make bounded progress on the same shared tree.
+    #[cfg(target_arch = "wasm32")]
+    pub fn step(&self, rng: &mut impl Rng, iterations: u32) {
+        let mut mcts = self.shared.lock().unwrap();
+        for _ in 0..iterations {
+            mcts.iterate(rng);
+        }
+    }
+
+    pub fn visit_counts(&self) -> Vec<(Action, u32)> {
+        self.shared.lock().unwrap().visit_counts()
+    }
+
+    pub fn total_iterations(&self) -> u32 {
+        self.shared.lock().unwrap().total_iterations()
+    }
+
+    pub fn best_action(&self) -> Option<Action> {
+        self.shared.lock().unwrap().best_action().copied()
+    }
+
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for BackgroundSearch {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
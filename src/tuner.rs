@@ -0,0 +1,155 @@
+//! Offline genetic tuning of the MCTS rollout policy's feature weights
+//! ("Trained Bot" mode, toggled in `Settings`).
+//!
+//! A population of weight vectors plays round-robin self-play games against
+//! each other using the weighted rollout policy directly (no tree search, so
+//! a generation evaluates quickly). The top fraction survive as parents;
+//! children are produced by per-gene crossover plus Gaussian mutation whose
+//! size decays each generation, the usual "explore wide, then fine-tune"
+//! shape for a genetic search.
+
+use rand::Rng;
+
+use crate::{
+    hobogo::Board,
+    mcts::{self, GameState, Weights, NUM_FEATURES},
+};
+
+pub const POPULATION_SIZE: usize = 20;
+
+/// wasm32 has no threads, so `app::TrainingJob` runs [`evolve`] synchronously
+/// on click; this much smaller population keeps that run from freezing the
+/// page for more than a moment.
+pub const WASM_POPULATION_SIZE: usize = 8;
+
+const BOARD_SIZE: i32 = 9;
+const NUM_PLAYERS: usize = 2;
+const PARENT_FRACTION: f32 = 0.3;
+const INITIAL_MUTATION_SIGMA: f32 = 0.5;
+const MUTATION_DECAY: f32 = 0.9;
+
+fn random_weights(rng: &mut impl Rng) -> Weights {
+    let mut weights = [0.0; NUM_FEATURES];
+    for w in &mut weights {
+        *w = rng.gen_range(-1.0..1.0);
+    }
+    weights
+}
+
+/// Plays one game between `a` (player 0) and `b` (player 1) to completion
+/// using only the weighted rollout policy, and returns the winner's index
+/// (0 or 1). A tie in points counts as a win for player 0.
+fn play_game(a: &Weights, b: &Weights, rng: &mut impl Rng) -> usize {
+    let mut state = GameState {
+        board: Board::new(BOARD_SIZE, BOARD_SIZE),
+        next_player: 0,
+        num_players: NUM_PLAYERS,
+    };
+
+    let mut passes_in_a_row = 0;
+    while !state.board.is_game_over(state.num_players) && passes_in_a_row < state.num_players {
+        let weights = if state.next_player == 0 { a } else { b };
+        let actions = state.legal_actions();
+        let action = mcts::weighted_choice(&state, &actions, weights, rng);
+        passes_in_a_row = if action == mcts::Action::Pass {
+            passes_in_a_row + 1
+        } else {
+            0
+        };
+        state = state.apply(action);
+    }
+
+    let points = state.board.points(state.num_players);
+    if points.get(0).copied().unwrap_or(0) >= points.get(1).copied().unwrap_or(0) {
+        0
+    } else {
+        1
+    }
+}
+
+/// Round-robin: every pair plays `2 * games_per_matchup` games, each side
+/// going first in `games_per_matchup` of them, +1 win per victory.
+fn evaluate_population(population: &[Weights], games_per_matchup: usize, rng: &mut impl Rng) -> Vec<f32> {
+    let mut wins = vec![0.0f32; population.len()];
+    for i in 0..population.len() {
+        for j in (i + 1)..population.len() {
+            for _ in 0..games_per_matchup {
+                if play_game(&population[i], &population[j], rng) == 0 {
+                    wins[i] += 1.0;
+                } else {
+                    wins[j] += 1.0;
+                }
+                if play_game(&population[j], &population[i], rng) == 0 {
+                    wins[j] += 1.0;
+                } else {
+                    wins[i] += 1.0;
+                }
+            }
+        }
+    }
+    wins
+}
+
+fn gaussian(rng: &mut impl Rng, sigma: f32) -> f32 {
+    // Box-Muller: no extra crate needed for a single Gaussian sample.
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen_range(0.0..1.0);
+    sigma * (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+fn next_generation(parents: &[Weights], population_size: usize, sigma: f32, rng: &mut impl Rng) -> Vec<Weights> {
+    let mut next_gen: Vec<Weights> = parents.to_vec();
+    while next_gen.len() < population_size {
+        let parent_a = parents[rng.gen_range(0..parents.len())];
+        let parent_b = parents[rng.gen_range(0..parents.len())];
+        let mut child = [0.0; NUM_FEATURES];
+        for gene in 0..NUM_FEATURES {
+            child[gene] = if rng.gen_bool(0.5) {
+                parent_a[gene]
+            } else {
+                parent_b[gene]
+            };
+            child[gene] += gaussian(rng, sigma);
+        }
+        next_gen.push(child);
+    }
+    next_gen
+}
+
+/// Runs the genetic tuner for `generations` rounds over a population of
+/// `population_size` weight vectors and returns the best one found. Intended
+/// to run on a background thread (see `app::TrainingJob`) -- even a modest
+/// `population_size`/`generations` plays many thousands of self-play games,
+/// so callers should keep both bounded (see `POPULATION_SIZE` vs.
+/// `WASM_POPULATION_SIZE`). `on_generation` is called with the number of
+/// completed generations (`1..=generations`) after each one finishes, so a
+/// caller on a background thread can surface progress.
+pub fn evolve(
+    generations: usize,
+    games_per_matchup: usize,
+    population_size: usize,
+    rng: &mut impl Rng,
+    mut on_generation: impl FnMut(usize),
+) -> Weights {
+    let mut population: Vec<Weights> = (0..population_size).map(|_| random_weights(rng)).collect();
+    let mut sigma = INITIAL_MUTATION_SIGMA;
+    let num_parents = ((population_size as f32 * PARENT_FRACTION) as usize).max(2);
+
+    let mut scores = evaluate_population(&population, games_per_matchup, rng);
+    for generation in 0..generations {
+        let mut ranked: Vec<usize> = (0..population.len()).collect();
+        ranked.sort_by(|&a, &b| scores[b].partial_cmp(&scores[a]).unwrap());
+
+        let parents: Vec<Weights> = ranked[..num_parents].iter().map(|&i| population[i]).collect();
+        population = next_generation(&parents, population_size, sigma, rng);
+        sigma *= MUTATION_DECAY;
+
+        scores = evaluate_population(&population, games_per_matchup, rng);
+        on_generation(generation + 1);
+    }
+
+    let best = (0..population.len())
+        .max_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap())
+        .expect("population is never empty");
+    population[best]
+}